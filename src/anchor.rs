@@ -0,0 +1,212 @@
+//! `AnchorHash` support arbitrary bucket removal (and re-addition), unlike plain jump
+//! consistent hash which can only shrink from the tail.
+//!
+//! From the paper "AnchorHash: A Scalable Consistent Hash" by Mendelson, Vargaftik, Barabash,
+//! Lorenz, Keslassy, Orda (2019).
+
+/// A constant from a 64 bit linear congruential generator, reused here to fold a seed into a
+/// key the same way [`crate::LCG_CONSTANT`] is used to drive the jump-hash loop.
+const SEED_MIX_CONSTANT: u64 = 2862933555777941757;
+
+/// mixes `key` with `seed` and finalizes with the MurmurHash3 `fmix64` avalanche so that nearby
+/// seeds produce decorrelated outputs
+fn seeded_hash(key: u64, seed: u64) -> u64 {
+    let mut h = key ^ seed.wrapping_mul(SEED_MIX_CONSTANT);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xff51afd7ed558ccd);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xc4ceb9fe1a85ec53);
+    h ^= h >> 33;
+    h
+}
+
+/// A consistent-hash ring over a fixed `capacity` of bucket slots, of which some subset is
+/// "working" at any given time. Unlike [`crate::jump_hash_from_u64`], buckets can be removed
+/// and re-added in any order (not just from the tail) while keeping remaps minimal: removing
+/// bucket `b` only remaps the keys that were already assigned to `b`.
+///
+/// This is a direct implementation of the `A`/`R`/`K` bookkeeping from the AnchorHash paper:
+/// `A[b] == 0` marks a working bucket, `A[b] == |W|` (the working-set size at the moment of
+/// removal) marks a removed one, `R` is a LIFO stack of removed buckets available for re-use,
+/// and `K[b]` records the working bucket that absorbs `b`'s keys.
+pub struct AnchorHash {
+    capacity: u32,
+    working_count: u32,
+    a: Vec<u32>,
+    r: Vec<u32>,
+    k: Vec<u32>,
+    // slot bookkeeping: which bucket currently occupies each working "slot", and the reverse
+    // mapping, so an arbitrary bucket can be swapped out of the working set in O(1).
+    slot_to_bucket: Vec<u32>,
+    bucket_to_slot: Vec<u32>,
+}
+
+impl AnchorHash {
+    /// creates an `AnchorHash` with `buckets` initially working out of a total `capacity`.
+    ///
+    /// expects `capacity >= buckets` and `buckets >= 1`
+    pub fn new(buckets: u32, capacity: u32) -> AnchorHash {
+        assert!(buckets >= 1);
+        assert!(capacity >= buckets);
+        let mut a = vec![0; capacity as usize];
+        let mut r = Vec::with_capacity((capacity - buckets) as usize);
+        let k = vec![0; capacity as usize];
+        let mut slot_to_bucket = vec![0; capacity as usize];
+        let mut bucket_to_slot = vec![0; capacity as usize];
+
+        for b in 0..buckets {
+            slot_to_bucket[b as usize] = b;
+            bucket_to_slot[b as usize] = b;
+        }
+        // buckets beyond the initial working set start out removed, available for add_bucket
+        for b in (buckets..capacity).rev() {
+            a[b as usize] = buckets;
+            r.push(b);
+        }
+
+        AnchorHash {
+            capacity,
+            working_count: buckets,
+            a,
+            r,
+            k,
+            slot_to_bucket,
+            bucket_to_slot,
+        }
+    }
+
+    /// returns the working bucket that `key` hashes to
+    pub fn get_bucket(&self, key: u64) -> u32 {
+        let mut b = (key % self.capacity as u64) as u32;
+        while self.a[b as usize] > 0 {
+            let seed = self.a[b as usize] as u64;
+            let mut h = (seeded_hash(key, seed) % seed) as u32;
+            while self.a[h as usize] >= self.a[b as usize] {
+                h = self.k[h as usize];
+            }
+            b = h;
+        }
+        b
+    }
+
+    /// removes `b` from the working set, remapping only the keys that were assigned to it.
+    ///
+    /// panics if `b` is out of range or already removed
+    pub fn remove_bucket(&mut self, b: u32) {
+        assert!(b < self.capacity);
+        assert_eq!(self.a[b as usize], 0, "bucket {} is not currently working", b);
+        assert!(self.working_count > 1, "cannot remove the only remaining working bucket");
+
+        let slot = self.bucket_to_slot[b as usize];
+        let last_slot = self.working_count - 1;
+
+        // the bucket that absorbs b's keys: normally whatever bucket occupied the last slot,
+        // swapped into b's freed slot. If b itself was already in the last slot there's
+        // nothing to swap, and the successor must still be some other working bucket so that
+        // K[b] is never b itself (which would make get_bucket's walk over K spin forever).
+        let successor = if slot != last_slot {
+            let last_bucket = self.slot_to_bucket[last_slot as usize];
+            self.slot_to_bucket[slot as usize] = last_bucket;
+            self.bucket_to_slot[last_bucket as usize] = slot;
+            last_bucket
+        } else {
+            self.slot_to_bucket[0]
+        };
+
+        self.working_count = last_slot;
+        self.a[b as usize] = self.working_count;
+        self.k[b as usize] = successor;
+        self.r.push(b);
+    }
+
+    /// re-adds the most recently removed bucket to the working set, returning its id
+    ///
+    /// panics if there is no removed bucket to re-add
+    pub fn add_bucket(&mut self) -> u32 {
+        let b = self.r.pop().expect("no removed bucket available to add back");
+        let slot = self.working_count;
+        self.slot_to_bucket[slot as usize] = b;
+        self.bucket_to_slot[b as usize] = slot;
+        self.working_count += 1;
+        self.a[b as usize] = 0;
+        b
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn all_keys_land_on_a_working_bucket() {
+        let anchor = AnchorHash::new(10, 20);
+        for key in 0..10_000u64 {
+            let b = anchor.get_bucket(key);
+            assert!(b < 10);
+        }
+    }
+
+    #[test]
+    fn removing_middle_bucket_only_remaps_its_own_keys() {
+        let mut anchor = AnchorHash::new(10, 20);
+        let num_keys = 10_000u64;
+        let before: Vec<u32> = (0..num_keys).map(|k| anchor.get_bucket(k)).collect();
+
+        anchor.remove_bucket(3);
+
+        for key in 0..num_keys {
+            let after = anchor.get_bucket(key);
+            assert_ne!(after, 3, "bucket 3 was removed, no key should land there");
+            if before[key as usize] != 3 {
+                assert_eq!(
+                    before[key as usize], after,
+                    "key {} was not on the removed bucket and should not have moved",
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn removing_a_bucket_in_the_last_slot_does_not_hang() {
+        // regression test: removing whichever bucket happens to occupy the last working slot
+        // used to set K[b] = b (a self-successor), which made get_bucket spin forever for any
+        // key whose intra-bucket draw landed back on b.
+        let mut anchor = AnchorHash::new(16, 64);
+        anchor.remove_bucket(4);
+        assert_eq!(anchor.add_bucket(), 4);
+        anchor.remove_bucket(4);
+        for key in 0..10_000u64 {
+            assert!(anchor.get_bucket(key) < 16);
+        }
+    }
+
+    #[test]
+    fn interleaved_removals_and_additions_never_hang() {
+        let mut anchor = AnchorHash::new(10, 20);
+        for &b in &[3, 7, 0, 9, 1] {
+            anchor.remove_bucket(b);
+            let re_added = anchor.add_bucket();
+            anchor.remove_bucket(re_added);
+            anchor.add_bucket();
+        }
+        for key in 0..10_000u64 {
+            assert!(anchor.get_bucket(key) < 10);
+        }
+    }
+
+    #[test]
+    fn add_bucket_reuses_removed_slot_and_restores_lookups() {
+        let mut anchor = AnchorHash::new(10, 20);
+        let num_keys = 5_000u64;
+        let before: Vec<u32> = (0..num_keys).map(|k| anchor.get_bucket(k)).collect();
+
+        anchor.remove_bucket(3);
+        let re_added = anchor.add_bucket();
+        assert_eq!(re_added, 3);
+
+        for key in 0..num_keys {
+            assert_eq!(before[key as usize], anchor.get_bucket(key));
+        }
+    }
+}
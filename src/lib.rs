@@ -1,11 +1,23 @@
 //!  implements Google's Jump Consistent Hash
 //! From the paper "A Fast, Minimal Memory, Consistent Hash Algorithm" by John Lamping, Eric Veach (2014).
 //! [Paper](http://arxiv.org/abs/1406.2294)
-use std::hash::{Hash, Hasher};
-use std::collections::hash_map::DefaultHasher;
+use std::collections::hash_map::{DefaultHasher, RandomState};
+use std::hash::{BuildHasher, Hash, Hasher};
+
+mod anchor;
+pub use anchor::AnchorHash;
+
+mod weighted;
+pub use weighted::WeightedJumpHash;
+
+mod ring;
+pub use ring::Ring;
 
 /// hashes an `&str` to a `u32` which is in the range of 0..buckets
 ///
+/// Uses `DefaultHasher` (SipHash) under the hood; if you're on a hot path and don't need
+/// SipHash's DoS resistance, prefer [`jump_hash_with`] with a faster `BuildHasher`.
+///
 /// expects a number of buckets greater than zero
 /// ```rust
 /// use jumpconsistenthash::jump_hash_from_str;
@@ -21,6 +33,45 @@ pub fn jump_hash_from_str(key: &str, buckets: u32) -> u32 {
     let key = hasher.finish();
     jump_hash_from_u64(key, buckets)
 }
+
+/// hashes an `&str` to a `u32` which is in the range of 0..buckets, using `std`'s
+/// `RandomState` rather than a fixed `DefaultHasher`.
+///
+/// expects a number of buckets greater than zero
+/// ```rust
+/// use jumpconsistenthash::jump_hash_from_str_randomized;
+/// let number_of_buckets = 10;
+/// let next_bucket = jump_hash_from_str_randomized("some_key", number_of_buckets);
+/// assert!(next_bucket < number_of_buckets);
+/// ```
+pub fn jump_hash_from_str_randomized(key: &str, buckets: u32) -> u32 {
+    jump_hash_with(key, buckets, &RandomState::new())
+}
+
+/// hashes an `&str` to a `u32` which is in the range of 0..buckets, using a caller-supplied
+/// [`BuildHasher`].
+///
+/// This is the generic counterpart of [`jump_hash_from_str`] (which hashes with
+/// `DefaultHasher` directly) and the function that [`jump_hash_from_str_randomized`] wraps.
+/// Sharding hot paths care
+/// about hashing speed, not SipHash's DoS resistance, so callers can plug in a fast hasher
+/// (e.g. an xxHash/xxh3 or aHash-style `BuildHasher`) while the jump-hash bucket math on the
+/// resulting `u64` stays exactly the same.
+///
+/// expects a number of buckets greater than zero
+/// ```rust
+/// use std::collections::hash_map::RandomState;
+/// use jumpconsistenthash::jump_hash_with;
+/// let number_of_buckets = 10;
+/// let build = RandomState::new();
+/// let next_bucket = jump_hash_with("some_key", number_of_buckets, &build);
+/// assert!(next_bucket < number_of_buckets);
+/// ```
+pub fn jump_hash_with<H: BuildHasher>(key: &str, buckets: u32, build: &H) -> u32 {
+    assert!(buckets >= 1);
+    let key = build.hash_one(key);
+    jump_hash_from_u64(key, buckets)
+}
 /// hashes a `u64` to a `u32` which is in the range of 0..buckets
 ///
 /// expects a number of buckets greater than zero
@@ -45,12 +96,55 @@ pub fn jump_hash_from_u64(key: u64, buckets: u32) -> u32 {
     b as u32
 }
 
+/// hashes a `u64` to a `u32` which is in the range of 0..buckets, under an independent
+/// permutation chosen by `seed`.
+///
+/// Plain jump hash always assigns a given key to the same bucket, which is fine for a single
+/// ring but means several independent rings (e.g. one per tenant) all agree with each other,
+/// defeating independent load balancing. Folding a seed into the key before the jump loop
+/// gives each ring its own permutation while keeping the within-ring minimal-movement
+/// property.
+///
+/// expects a number of buckets greater than zero
+/// ```rust
+/// use jumpconsistenthash::jump_hash_seeded;
+/// let number_of_buckets = 10;
+/// let bucket = jump_hash_seeded(52, number_of_buckets, 1234);
+/// assert!(bucket < number_of_buckets);
+/// ```
+pub fn jump_hash_seeded(key: u64, buckets: u32, seed: u64) -> u32 {
+    assert!(buckets >= 1);
+    let seeded_key = key ^ seed.wrapping_mul(LCG_CONSTANT);
+    jump_hash_from_u64(seeded_key, buckets)
+}
+
+/// hashes an `&str` to a `u32` which is in the range of 0..buckets, under an independent
+/// permutation chosen by `seed`.
+///
+/// unlike [`jump_hash_seeded`], the seed is folded into the hasher state rather than the key
+/// itself, since the `&str` hasn't been reduced to a `u64` yet.
+///
+/// expects a number of buckets greater than zero
+/// ```rust
+/// use jumpconsistenthash::jump_hash_from_str_seeded;
+/// let number_of_buckets = 10;
+/// let bucket = jump_hash_from_str_seeded("some_key", number_of_buckets, 1234);
+/// assert!(bucket < number_of_buckets);
+/// ```
+pub fn jump_hash_from_str_seeded(key: &str, buckets: u32, seed: u64) -> u32 {
+    assert!(buckets >= 1);
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    seed.hash(&mut hasher);
+    let key = hasher.finish();
+    jump_hash_from_u64(key, buckets)
+}
 
 /// A constant from a 64 bit linear congruential generator found in the original paper
 /// but explained here:
 /// https://nuclear.llnl.gov/CNP/rng/rngman/node4.html
 /// Jump hash has a 64 bit pseudo-random generator 'embedded' in it
-const LCG_CONSTANT: u64 = 2862933555777941757;
+pub(crate) const LCG_CONSTANT: u64 = 2862933555777941757;
 
 #[cfg(test)]
 mod test {
@@ -67,6 +161,47 @@ mod test {
         jump_hash_from_str("foobar", 0);
     }
 
+    #[test]
+    fn different_seeds_decorrelate_bucket_assignments() {
+        let num_buckets = 10;
+        let num_keys = 100_000u64;
+        let mut matching = 0u64;
+        for key in 0..num_keys {
+            let a = jump_hash_seeded(key, num_buckets, 1);
+            let b = jump_hash_seeded(key, num_buckets, 2);
+            if a == b {
+                matching += 1;
+            }
+        }
+        // with decorrelated seeds, two independent rings should agree about as often as chance
+        // (1/num_buckets), not anywhere near always
+        let matching_share = matching as f64 / num_keys as f64;
+        assert!(
+            matching_share < 2.0 / num_buckets as f64,
+            "seeded rings agreed on {} of keys, expected close to chance (1/{})",
+            matching_share,
+            num_buckets
+        );
+    }
+
+    #[test]
+    fn jump_hash_with_matches_default_hasher_plumbing() {
+        // a BuildHasher that produces a DefaultHasher should agree with jump_hash_from_str,
+        // since that's exactly what jump_hash_from_str does under the hood
+        struct DefaultHasherBuilder;
+        impl BuildHasher for DefaultHasherBuilder {
+            type Hasher = DefaultHasher;
+            fn build_hasher(&self) -> DefaultHasher {
+                DefaultHasher::new()
+            }
+        }
+        for num_buckets in 1..50 {
+            let expected = jump_hash_from_str("some_key", num_buckets);
+            let actual = jump_hash_with("some_key", num_buckets, &DefaultHasherBuilder);
+            assert_eq!(expected, actual);
+        }
+    }
+
     #[test]
     fn bucket_chosen_always_within_range() {
         for num_buckets in 1..500 {
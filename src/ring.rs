@@ -0,0 +1,123 @@
+//! A high-level consistent-hash ring over named nodes, built on top of
+//! [`crate::jump_hash_from_u64`].
+//!
+//! Plain jump hash maps a key to a bucket index; `Ring<T>` adds the node bookkeeping on top so
+//! callers don't have to build it themselves: the bucket index is resolved to a node identifier,
+//! and replica selection picks `n` distinct nodes for replication.
+
+use crate::{jump_hash_from_u64, LCG_CONSTANT};
+
+/// A consistent-hash ring over an ordered list of node identifiers.
+pub struct Ring<T> {
+    nodes: Vec<T>,
+}
+
+impl<T> Ring<T> {
+    /// creates a `Ring` over the given nodes, in the order provided
+    pub fn new(nodes: Vec<T>) -> Ring<T> {
+        Ring { nodes }
+    }
+
+    /// adds a node to the end of the ring
+    pub fn add_node(&mut self, node: T) {
+        self.nodes.push(node);
+    }
+
+    /// removes and returns the last node in the ring, if any
+    pub fn remove_last(&mut self) -> Option<T> {
+        self.nodes.pop()
+    }
+
+    /// returns the node `key` maps to
+    ///
+    /// expects the ring to be non-empty
+    pub fn node_for(&self, key: u64) -> &T {
+        assert!(!self.nodes.is_empty());
+        let idx = jump_hash_from_u64(key, self.nodes.len() as u32);
+        &self.nodes[idx as usize]
+    }
+
+    /// returns `n` distinct nodes for `key`, in replica order; `nodes_for(key, n)[0]` is always
+    /// equal to `node_for(key)`.
+    ///
+    /// each replica slot re-seeds the key by mixing in the replica index (using the same LCG
+    /// constant the jump-hash loop itself is built on) and re-runs jump hash over the node
+    /// count, skipping buckets already chosen, so the result has no duplicates even when `n`
+    /// approaches the node count. The re-seeded search is capped at a bounded number of
+    /// attempts; any slots still unfilled after that are taken by a linear scan over the
+    /// remaining nodes, so worst-case latency stays deterministic.
+    ///
+    /// expects `n` to be no greater than the number of nodes in the ring
+    pub fn nodes_for(&self, key: u64, n: usize) -> Vec<&T> {
+        assert!(n <= self.nodes.len());
+        let buckets = self.nodes.len() as u32;
+        let mut chosen = Vec::with_capacity(n);
+        let mut replica: u64 = 0;
+        let max_replica_attempts = buckets as u64 * 4 + 16;
+        while chosen.len() < n && replica < max_replica_attempts {
+            let seeded_key = key ^ replica.wrapping_mul(LCG_CONSTANT);
+            let idx = jump_hash_from_u64(seeded_key, buckets);
+            if !chosen.contains(&idx) {
+                chosen.push(idx);
+            }
+            replica += 1;
+        }
+        for idx in 0..buckets {
+            if chosen.len() == n {
+                break;
+            }
+            if !chosen.contains(&idx) {
+                chosen.push(idx);
+            }
+        }
+        chosen.into_iter().map(|idx| &self.nodes[idx as usize]).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn primary_replica_matches_node_for() {
+        let ring = Ring::new(vec!["a", "b", "c", "d", "e"]);
+        for key in 0..1_000u64 {
+            let replicas = ring.nodes_for(key, 3);
+            assert_eq!(replicas[0], ring.node_for(key));
+        }
+    }
+
+    #[test]
+    fn replicas_are_distinct() {
+        let ring = Ring::new((0..10).collect::<Vec<u32>>());
+        for key in 0..1_000u64 {
+            let replicas = ring.nodes_for(key, 8);
+            let mut seen = replicas.clone();
+            seen.sort();
+            seen.dedup();
+            assert_eq!(seen.len(), replicas.len(), "replicas must be distinct");
+        }
+    }
+
+    #[test]
+    fn n_equal_to_node_count_still_returns_every_node() {
+        // n == node count is the worst case for the re-seeded replica search; the fallback
+        // linear scan must pick up any slots it doesn't fill in bounded attempts.
+        let ring = Ring::new((0..20).collect::<Vec<u32>>());
+        for key in 0..200u64 {
+            let mut replicas = ring.nodes_for(key, 20);
+            replicas.sort();
+            replicas.dedup();
+            assert_eq!(replicas.len(), 20);
+        }
+    }
+
+    #[test]
+    fn add_node_and_remove_last_change_ring_size() {
+        let mut ring = Ring::new(vec!["a", "b"]);
+        ring.add_node("c");
+        assert_eq!(ring.nodes_for(42, 3).len(), 3);
+        assert_eq!(ring.remove_last(), Some("c"));
+        assert_eq!(ring.nodes_for(42, 2).len(), 2);
+    }
+}
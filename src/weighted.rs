@@ -0,0 +1,120 @@
+//! Weighted buckets for jump consistent hash, so differently-sized shards receive
+//! proportionally-sized shares of keys.
+//!
+//! Plain [`crate::jump_hash_from_u64`] assumes every bucket is the same size. `WeightedJumpHash`
+//! maps each logical bucket to a contiguous run of virtual buckets sized by its weight, runs
+//! jump hash over the total virtual bucket count, then translates the virtual index back to a
+//! logical bucket via a prefix-sum lookup. Changing one bucket's weight (or adding/removing a
+//! bucket at the end) only perturbs the virtual buckets that sit at or after the change: keys
+//! of the buckets before it never move. Reshuffling is minimal only for a change at the end
+//! (the last bucket's weight, or adding/removing a last bucket); changing an earlier bucket's weight shifts the virtual ranges of every bucket after
+//! it, so keys also move between those existing buckets.
+
+use crate::jump_hash_from_u64;
+
+/// A jump-hash ring over buckets of unequal weight.
+///
+/// Each bucket `i` gets, in expectation, a `weights[i] / sum(weights)` share of keys.
+pub struct WeightedJumpHash {
+    weights: Vec<u32>,
+    // prefix_sums[i] = sum(weights[0..=i]), i.e. the exclusive upper bound of the virtual
+    // bucket range owned by logical bucket i
+    prefix_sums: Vec<u32>,
+}
+
+impl WeightedJumpHash {
+    /// creates a `WeightedJumpHash` from per-bucket weights.
+    ///
+    /// expects at least one weight, all of them greater than zero
+    pub fn new(weights: Vec<u32>) -> WeightedJumpHash {
+        assert!(!weights.is_empty());
+        assert!(weights.iter().all(|&w| w > 0));
+        let prefix_sums = Self::build_prefix_sums(&weights);
+        WeightedJumpHash { weights, prefix_sums }
+    }
+
+    fn build_prefix_sums(weights: &[u32]) -> Vec<u32> {
+        let mut running = 0u32;
+        weights
+            .iter()
+            .map(|&w| {
+                running += w;
+                running
+            })
+            .collect()
+    }
+
+    fn total_virtual_buckets(&self) -> u32 {
+        *self.prefix_sums.last().expect("weights is non-empty")
+    }
+
+    /// returns the logical bucket `key` hashes to, with probability proportional to its weight
+    pub fn bucket_for(&self, key: u64) -> u32 {
+        let virtual_bucket = jump_hash_from_u64(key, self.total_virtual_buckets());
+        // first logical bucket whose virtual range extends past `virtual_bucket`
+        self.prefix_sums
+            .partition_point(|&upper_bound| upper_bound <= virtual_bucket) as u32
+    }
+
+    /// updates a single bucket's weight, rebuilding the virtual-bucket prefix sums.
+    ///
+    /// expects `bucket` to be in range and `weight` greater than zero
+    pub fn update_weight(&mut self, bucket: usize, weight: u32) {
+        assert!(weight > 0);
+        self.weights[bucket] = weight;
+        self.prefix_sums = Self::build_prefix_sums(&self.weights);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bucket_chosen_always_within_range() {
+        let weighted = WeightedJumpHash::new(vec![1, 2, 3, 4]);
+        for key in 0..100_000u64 {
+            assert!(weighted.bucket_for(key) < 4);
+        }
+    }
+
+    #[test]
+    fn empirical_distribution_matches_weight_ratios() {
+        let weights = vec![1u32, 2, 3, 4];
+        let weighted = WeightedJumpHash::new(weights.clone());
+        let total_weight: u32 = weights.iter().sum();
+
+        let num_keys = 100_000u64;
+        let mut counts = vec![0u64; weights.len()];
+        for key in 0..num_keys {
+            counts[weighted.bucket_for(key) as usize] += 1;
+        }
+
+        for (bucket, &weight) in weights.iter().enumerate() {
+            let expected_share = weight as f64 / total_weight as f64;
+            let actual_share = counts[bucket] as f64 / num_keys as f64;
+            assert!(
+                (expected_share - actual_share).abs() < 0.02,
+                "bucket {} expected share {} but got {}",
+                bucket,
+                expected_share,
+                actual_share
+            );
+        }
+    }
+
+    #[test]
+    fn update_weight_changes_future_distribution() {
+        let mut weighted = WeightedJumpHash::new(vec![1, 1]);
+        weighted.update_weight(0, 9);
+
+        let num_keys = 100_000u64;
+        let mut counts = [0u64; 2];
+        for key in 0..num_keys {
+            counts[weighted.bucket_for(key) as usize] += 1;
+        }
+
+        let bucket_0_share = counts[0] as f64 / num_keys as f64;
+        assert!((bucket_0_share - 0.9).abs() < 0.02);
+    }
+}